@@ -0,0 +1,220 @@
+use crate::command::Direction;
+use crate::json::{CellType, Position, Powerup, Weapon};
+
+/// The game state a bot actually reasons over, once the competition's wire
+/// format has been stripped of its JSON quirks (nested rows, a redundant
+/// per-cell `occupier`, separate `myPlayer`/`opponents` shapes).
+///
+/// Built from a `JsonState` via `JsonState::to_game_state`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameBoard {
+    pub current_round: u32,
+    pub max_rounds: u32,
+    pub map_size: u32,
+    pub current_worm_id: u32,
+    pub consecutive_do_nothing_count: u32,
+    pub players: [Player; 2],
+    pub powerups: Vec<(Position, Powerup)>,
+    pub map: Vec<CellType>
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Player {
+    pub id: u32,
+    pub score: u32,
+    pub worms: Vec<Worm>
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Worm {
+    pub id: u32,
+    pub health: u32,
+    pub position: Position,
+    pub digging_range: u32,
+    pub movement_range: u32,
+    /// `None` for opponent worms, whose weapon stats the wire format
+    /// doesn't report.
+    pub weapon: Option<Weapon>
+}
+
+impl GameBoard {
+    /// The player reasoning about this board. `players[1]` is the opponent.
+    fn me(&self) -> &Player {
+        &self.players[0]
+    }
+
+    /// # Panics
+    ///
+    /// This function panics if the board's current_worm_id
+    /// does not appear in the player's worms. This should never
+    /// happen for a valid board.
+    pub fn active_worm(&self) -> &Worm {
+        self.me().worms.iter()
+            .find(|w| w.id == self.current_worm_id)
+            .expect("The current active worm id was not found in the player's worms")
+    }
+
+    /// The flat `map` index for `pos`, or `None` if `pos` is off the map.
+    pub(crate) fn cell_index(&self, pos: &Position) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x as u32 >= self.map_size || pos.y as u32 >= self.map_size {
+            return None;
+        }
+
+        Some((pos.y as u32 * self.map_size + pos.x as u32) as usize)
+    }
+
+    /// Returns the terrain at `pos`, or `None` if `pos` is off the map.
+    /// Signed coordinates mean an out-of-range probe (from ray or dig
+    /// analysis that has stepped past an edge) lands here rather than
+    /// panicking or silently wrapping.
+    pub fn cell_at(&self, pos: &Position) -> Option<&CellType> {
+        self.cell_index(pos).and_then(|i| self.map.get(i))
+    }
+
+    /// Mutable access to the worm with `worm_id` on `self.players[player_index]`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `worm_id` does not appear in that player's worms.
+    pub(crate) fn worm_mut(&mut self, player_index: usize, worm_id: u32) -> &mut Worm {
+        self.players[player_index].worms.iter_mut()
+            .find(|w| w.id == worm_id)
+            .expect("The provided worm id was not found in the player's worms")
+    }
+
+    /// Immutable access to the worm with `worm_id` on `self.players[player_index]`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `worm_id` does not appear in that player's worms.
+    pub(crate) fn worm(&self, player_index: usize, worm_id: u32) -> &Worm {
+        self.players[player_index].worms.iter()
+            .find(|w| w.id == worm_id)
+            .expect("The provided worm id was not found in the player's worms")
+    }
+
+    /// Returns the worm (from either player) occupying `pos`, if any.
+    fn worm_at(&self, pos: &Position) -> Option<&Worm> {
+        self.players.iter()
+            .flat_map(|p| p.worms.iter())
+            .find(|w| &w.position == pos)
+    }
+
+    /// Returns every opponent worm the active worm can legally shoot this
+    /// round, paired with the direction to fire in.
+    ///
+    /// For each opponent, `direction_to` derives the ray to walk (if the
+    /// opponent is aligned and in range at all); the cells strictly
+    /// between the two worms must then be clear `Air` with no other worm
+    /// standing in the way.
+    pub fn shootable_opponents(&self) -> Vec<(&Worm, Direction)> {
+        let worm = self.active_worm();
+        let weapon = worm.weapon.as_ref().expect("The active worm must have a weapon");
+
+        self.players[1].worms.iter()
+            .filter_map(|opponent| {
+                let distance = worm.position.chebyshev_distance(&opponent.position);
+                if distance == 0 || distance as u32 > weapon.range {
+                    return None;
+                }
+
+                let direction = worm.position.direction_to(&opponent.position)?;
+
+                let path_is_clear = (1..distance).all(|step| {
+                    let position = worm.position.step(direction, step);
+                    self.cell_at(&position) == Some(&CellType::Air) && self.worm_at(&position).is_none()
+                });
+
+                if path_is_clear {
+                    Some((opponent, direction))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn board_with_active_worm_weapon_range(range: u32) -> GameBoard {
+        GameBoard {
+            current_round: 0,
+            max_rounds: 200,
+            map_size: 5,
+            current_worm_id: 1,
+            consecutive_do_nothing_count: 0,
+            players: [
+                Player {
+                    id: 1,
+                    score: 100,
+                    worms: vec!{
+                        Worm {
+                            id: 1,
+                            health: 100,
+                            position: Position { x: 2, y: 2 },
+                            digging_range: 1,
+                            movement_range: 1,
+                            weapon: Some(Weapon { damage: 5, range })
+                        }
+                    }
+                },
+                Player {
+                    id: 2,
+                    score: 100,
+                    worms: vec!{
+                        Worm {
+                            id: 1,
+                            health: 100,
+                            position: Position { x: 4, y: 2 },
+                            digging_range: 1,
+                            movement_range: 1,
+                            weapon: None
+                        }
+                    }
+                }
+            ],
+            powerups: Vec::new(),
+            map: vec![CellType::Air; 25]
+        }
+    }
+
+    #[test]
+    fn cell_at_returns_none_off_the_map_in_either_direction() {
+        let board = board_with_active_worm_weapon_range(3);
+
+        assert_eq!(board.cell_at(&Position { x: -1, y: 0 }), None);
+        assert_eq!(board.cell_at(&Position { x: 0, y: -1 }), None);
+        assert_eq!(board.cell_at(&Position { x: 5, y: 0 }), None);
+        assert_eq!(board.cell_at(&Position { x: 0, y: 5 }), None);
+        assert_eq!(board.cell_at(&Position { x: 0, y: 0 }), Some(&CellType::Air));
+    }
+
+    #[test]
+    fn shootable_opponents_reports_worms_on_a_clear_ray_within_range() {
+        let board = board_with_active_worm_weapon_range(3);
+
+        let hits = board.shootable_opponents();
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, 1);
+        assert_eq!(hits[0].1, Direction::E);
+    }
+
+    #[test]
+    fn shootable_opponents_ignores_worms_out_of_range() {
+        let board = board_with_active_worm_weapon_range(1);
+
+        assert_eq!(board.shootable_opponents(), Vec::new());
+    }
+
+    #[test]
+    fn shootable_opponents_ignores_worms_blocked_by_dirt() {
+        let mut board = board_with_active_worm_weapon_range(3);
+        board.map[2 * 5 + 3] = CellType::Dirt;
+
+        assert_eq!(board.shootable_opponents(), Vec::new());
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt;
+
+/// One of the eight compass directions a worm can shoot in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Direction::N => "N",
+            Direction::NE => "NE",
+            Direction::E => "E",
+            Direction::SE => "SE",
+            Direction::S => "S",
+            Direction::SW => "SW",
+            Direction::W => "W",
+            Direction::NW => "NW"
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The command a bot prints each round to drive its active worm.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Move(u32, u32),
+    Dig(u32, u32),
+    Shoot(Direction),
+    DoNothing
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::Move(x, y) => write!(f, "move {} {}", x, y),
+            Command::Dig(x, y) => write!(f, "dig {} {}", x, y),
+            Command::Shoot(direction) => write!(f, "shoot {}", direction),
+            Command::DoNothing => write!(f, "nothing")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn commands_render_in_the_engine_wire_format() {
+        assert_eq!(Command::Move(3, 4).to_string(), "move 3 4");
+        assert_eq!(Command::Dig(12, 0).to_string(), "dig 12 0");
+        assert_eq!(Command::Shoot(Direction::NE).to_string(), "shoot NE");
+        assert_eq!(Command::DoNothing.to_string(), "nothing");
+    }
+
+    #[test]
+    fn directions_render_as_their_compass_abbreviation() {
+        assert_eq!(Direction::N.to_string(), "N");
+        assert_eq!(Direction::NE.to_string(), "NE");
+        assert_eq!(Direction::E.to_string(), "E");
+        assert_eq!(Direction::SE.to_string(), "SE");
+        assert_eq!(Direction::S.to_string(), "S");
+        assert_eq!(Direction::SW.to_string(), "SW");
+        assert_eq!(Direction::W.to_string(), "W");
+        assert_eq!(Direction::NW.to_string(), "NW");
+    }
+}
@@ -5,41 +5,48 @@ use std::error::Error;
 use serde::{Serialize, Deserialize};
 use serde_json;
 
-pub fn read_state_from_json_file(filename: &str) -> Result<State, Box<Error>> {
+use crate::board::{GameBoard, Player, Worm};
+use crate::command::Direction;
+
+pub fn read_state_from_json_file(filename: &str) -> Result<GameBoard, Box<dyn Error>> {
     let mut file = File::open(filename)?;
     let mut content = String::new();
     file.read_to_string(&mut content)?;
-    let state: State = serde_json::from_str(content.as_ref())?;
+    let state: JsonState = serde_json::from_str(content.as_ref())?;
 
-    Ok(state)
+    Ok(state.to_game_state())
 }
 
 
+/// The shape the competition's engine sends over the wire each round.
+///
+/// This struct and its fields exist purely as a deserialization target —
+/// reach for `GameBoard` (via `to_game_state`) to reason about a round.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct State {
+pub struct JsonState {
     pub current_round: u32,
     pub max_rounds: u32,
     pub map_size: u32,
     pub current_worm_id: u32,
     pub consecutive_do_nothing_count: u32,
-    pub my_player: Player,
-    pub opponents: Vec<Opponent>,
-    pub map: Vec<Vec<Cell>>
+    pub my_player: JsonPlayer,
+    pub opponents: Vec<JsonOpponent>,
+    pub map: Vec<Vec<JsonCell>>
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct Player {
+pub struct JsonPlayer {
     pub id: u32,
     pub score: u32,
     pub health: u32,
-    pub worms: Vec<PlayerWorm>
+    pub worms: Vec<JsonPlayerWorm>
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct PlayerWorm {
+pub struct JsonPlayerWorm {
     pub id: u32,
     pub health: u32,
     pub position: Position,
@@ -50,15 +57,15 @@ pub struct PlayerWorm {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct Opponent {
+pub struct JsonOpponent {
     pub id: u32,
     pub score: u32,
-    pub worms: Vec<OpponentWorm>
+    pub worms: Vec<JsonOpponentWorm>
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct OpponentWorm {
+pub struct JsonOpponentWorm {
     pub id: u32,
     pub health: u32,
     pub position: Position,
@@ -68,12 +75,12 @@ pub struct OpponentWorm {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct Cell {
-    pub x: u32,
-    pub y: u32,
+pub struct JsonCell {
+    pub x: i32,
+    pub y: i32,
     #[serde(rename = "type")]
     pub cell_type: CellType,
-    pub occupier: Option<CellWorm>,
+    pub occupier: Option<JsonCellWorm>,
     pub powerup: Option<Powerup>
 }
 
@@ -88,7 +95,7 @@ pub enum CellType {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(untagged)]
 #[serde(rename_all = "camelCase")]
-pub enum CellWorm {
+pub enum JsonCellWorm {
     #[serde(rename_all = "camelCase")]
     PlayerWorm {
         id: u32,
@@ -127,8 +134,8 @@ pub enum PowerupType {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Position {
-    pub x: u32,
-    pub y: u32
+    pub x: i32,
+    pub y: i32
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -138,56 +145,155 @@ pub struct Weapon {
     pub range: u32
 }
 
-impl State {
-    /// # Panics
+impl JsonState {
+    /// Converts the wire representation of a round into the `GameBoard`
+    /// a bot reasons over, flattening the nested map rows, dropping the
+    /// per-cell `occupier` (already captured by each player's worms), and
+    /// collecting powerups into their own list.
     ///
-    /// This function panics if the state's current_worm_id
-    /// does not appear in the player's worms. This should never
-    /// happen for valid state files.
-    pub fn active_worm(&self) -> &PlayerWorm {
-        self.my_player.worms.iter()
-            .find(|w| w.id == self.current_worm_id)
-            .expect("The current active worm id was not found in the player's worms")
-    }
-
     /// # Panics
     ///
-    /// This function panics if the provided position is out of
-    /// bounds, or the cell does not appear in the map.
-    pub fn cell_at(&self, pos: &Position) -> &Cell {
-        self.map.iter()
-            .flatten()
-            .find(|c| c.x == pos.x && c.y == pos.y)
-            .expect("The provided position is out of bounds")
+    /// This function panics if `opponents` is empty. The engine always
+    /// reports exactly one opponent in a 1v1 match.
+    pub fn to_game_state(self) -> GameBoard {
+        let mut map = Vec::with_capacity(self.map.len() * self.map_size as usize);
+        let mut powerups = Vec::new();
+
+        for row in self.map {
+            for cell in row {
+                if let Some(powerup) = cell.powerup {
+                    powerups.push((Position { x: cell.x, y: cell.y }, powerup));
+                }
+                map.push(cell.cell_type);
+            }
+        }
+
+        let opponent = self.opponents.into_iter().next()
+            .expect("A state must have exactly one opponent");
+
+        GameBoard {
+            current_round: self.current_round,
+            max_rounds: self.max_rounds,
+            map_size: self.map_size,
+            current_worm_id: self.current_worm_id,
+            consecutive_do_nothing_count: self.consecutive_do_nothing_count,
+            players: [
+                Player {
+                    id: self.my_player.id,
+                    score: self.my_player.score,
+                    worms: self.my_player.worms.into_iter()
+                        .map(|w| Worm {
+                            id: w.id,
+                            health: w.health,
+                            position: w.position,
+                            digging_range: w.digging_range,
+                            movement_range: w.movement_range,
+                            weapon: Some(w.weapon)
+                        })
+                        .collect()
+                },
+                Player {
+                    id: opponent.id,
+                    score: opponent.score,
+                    worms: opponent.worms.into_iter()
+                        .map(|w| Worm {
+                            id: w.id,
+                            health: w.health,
+                            position: w.position,
+                            digging_range: w.digging_range,
+                            movement_range: w.movement_range,
+                            weapon: None
+                        })
+                        .collect()
+                }
+            ],
+            powerups,
+            map
+        }
     }
 }
 
 impl Position {
-    pub fn west(&self, distance: u32) -> Option<Position> {
-        self.x.checked_sub(distance)
-            .map(|x| Position {
-                x, y: self.y
-            })
+    /// These compass helpers deliberately never fail:
+    /// `x`/`y` are signed, so stepping off the map just produces a
+    /// negative or overly large coordinate. Whether that position is
+    /// actually on the map is `GameBoard::cell_at`'s job, not this one's.
+    pub fn west(&self, distance: i32) -> Position {
+        Position { x: self.x - distance, y: self.y }
+    }
+    pub fn east(&self, distance: i32) -> Position {
+        Position { x: self.x + distance, y: self.y }
+    }
+    pub fn north(&self, distance: i32) -> Position {
+        Position { x: self.x, y: self.y - distance }
+    }
+    pub fn south(&self, distance: i32) -> Position {
+        Position { x: self.x, y: self.y + distance }
     }
-    pub fn east(&self, distance: u32, max: u32) -> Option<Position> {
-        self.x.checked_add(distance)
-            .filter(|&x| x < max)
-            .map(|x| Position {
-                x, y: self.y
-            })
+    pub fn north_east(&self, distance: i32) -> Position {
+        Position { x: self.x + distance, y: self.y - distance }
     }
-    pub fn north(&self, distance: u32) -> Option<Position> {
-        self.y.checked_sub(distance)
-            .map(|y| Position {
-                x: self.x, y
-            })
+    pub fn north_west(&self, distance: i32) -> Position {
+        Position { x: self.x - distance, y: self.y - distance }
     }
-    pub fn south(&self, distance: u32, max: u32) -> Option<Position> {
-        self.y.checked_add(distance)
-            .filter(|&y| y < max)
-            .map(|y| Position {
-                x: self.x, y
-            })
+    pub fn south_east(&self, distance: i32) -> Position {
+        Position { x: self.x + distance, y: self.y + distance }
+    }
+    pub fn south_west(&self, distance: i32) -> Position {
+        Position { x: self.x - distance, y: self.y + distance }
+    }
+
+    /// Returns the compass direction from `self` to `other`, or `None` if
+    /// `other` doesn't lie on a horizontal, vertical, or exact diagonal
+    /// line from `self`.
+    pub fn direction_to(&self, other: &Position) -> Option<Direction> {
+        let dx = other.x - self.x;
+        let dy = other.y - self.y;
+
+        if dx == 0 && dy == 0 {
+            return None;
+        }
+
+        if dx != 0 && dy != 0 && dx.abs() != dy.abs() {
+            return None;
+        }
+
+        let direction = match (dx.signum(), dy.signum()) {
+            (0, -1) => Direction::N,
+            (1, -1) => Direction::NE,
+            (1, 0) => Direction::E,
+            (1, 1) => Direction::SE,
+            (0, 1) => Direction::S,
+            (-1, 1) => Direction::SW,
+            (-1, 0) => Direction::W,
+            (-1, -1) => Direction::NW,
+            _ => unreachable!("dx and dy signums are each -1, 0, or 1")
+        };
+
+        Some(direction)
+    }
+
+    /// The Chebyshev (chessboard king-move) distance to `other`: for an
+    /// aligned `other`, the number of `step`s along `direction_to`'s
+    /// direction it takes to reach it.
+    pub fn chebyshev_distance(&self, other: &Position) -> i32 {
+        (other.x - self.x).abs().max((other.y - self.y).abs())
+    }
+
+    /// Steps `distance` cells from `self` in the given compass `direction`.
+    /// The single place that encodes the eight-direction mapping so ray
+    /// walkers (shooting, simulation) don't each hand-roll their own copy.
+    pub fn step(&self, direction: Direction, distance: i32) -> Position {
+        match direction {
+            Direction::N => self.north(distance),
+            Direction::NE => self.north_east(distance),
+            Direction::E => self.east(distance),
+            Direction::SE => self.south_east(distance),
+            Direction::S => self.south(distance),
+            Direction::SW => self.south_west(distance),
+            Direction::W => self.west(distance),
+            Direction::NW => self.north_west(distance)
+        }
     }
 }
 
@@ -312,18 +418,18 @@ mod test {
   ]
 }"#;
 
-        let expected = State {
+        let expected = JsonState {
             current_round: 0,
             max_rounds: 200,
             map_size: 33,
             current_worm_id: 1,
             consecutive_do_nothing_count: 0,
-            my_player: Player {
+            my_player: JsonPlayer {
                 id: 1,
                 score: 100,
                 health: 300,
                 worms: vec!{
-                    PlayerWorm {
+                    JsonPlayerWorm {
                         id: 1,
                         health: 100,
                         position: Position {
@@ -340,11 +446,11 @@ mod test {
                 }
             },
             opponents: vec!{
-                Opponent {
+                JsonOpponent {
                     id: 2,
                     score: 100,
                     worms: vec!{
-                        OpponentWorm {
+                        JsonOpponentWorm {
                             id: 1,
                             health: 100,
                             position: Position {
@@ -359,21 +465,21 @@ mod test {
             },
             map: vec!{
                 vec!{
-                    Cell {
+                    JsonCell {
                         x: 0,
                         y: 0,
                         cell_type: CellType::DeepSpace,
                         occupier: None,
                         powerup: None
                     },
-                    Cell {
+                    JsonCell {
                         x: 1,
                         y: 0,
                         cell_type: CellType::Air,
                         occupier: None,
                         powerup: None
                     },
-                    Cell {
+                    JsonCell {
                         x: 2,
                         y: 0,
                         cell_type: CellType::Dirt,
@@ -382,7 +488,7 @@ mod test {
                     }
                 },
                 vec!{
-                    Cell {
+                    JsonCell {
                         x: 0,
                         y: 1,
                         cell_type: CellType::Air,
@@ -392,11 +498,11 @@ mod test {
                             value: 5
                         })
                     },
-                    Cell {
+                    JsonCell {
                         x: 1,
                         y: 1,
                         cell_type: CellType::Air,
-                        occupier: Some(CellWorm::OpponentWorm {
+                        occupier: Some(JsonCellWorm::OpponentWorm {
                             id: 1,
                             player_id: 2,
                             health: 100,
@@ -409,11 +515,11 @@ mod test {
                         }),
                         powerup: None
                     },
-                    Cell {
+                    JsonCell {
                         x: 2,
                         y: 1,
                         cell_type: CellType::Air,
-                        occupier: Some(CellWorm::PlayerWorm {
+                        occupier: Some(JsonCellWorm::PlayerWorm {
                             id: 1,
                             player_id: 1,
                             health: 100,
@@ -434,8 +540,76 @@ mod test {
             }
         };
 
-        let parsed: State = serde_json::from_str(example).unwrap();
+        let parsed: JsonState = serde_json::from_str(example).unwrap();
 
         assert_eq!(parsed, expected, "Parsed value did not match the expected value.\nParsed = {:#?}\nExpected = {:#?}", parsed, expected);
     }
+
+    #[test]
+    fn to_game_state_flattens_the_map_and_drops_occupiers() {
+        let parsed: JsonState = serde_json::from_str(r#"
+{
+  "currentRound": 0,
+  "maxRounds": 200,
+  "mapSize": 2,
+  "currentWormId": 1,
+  "consecutiveDoNothingCount": 0,
+  "myPlayer": {
+    "id": 1,
+    "score": 100,
+    "health": 300,
+    "worms": [
+      { "id": 1, "health": 100, "position": { "x": 0, "y": 0 }, "weapon": { "damage": 1, "range": 3 }, "diggingRange": 1, "movementRange": 1 }
+    ]
+  },
+  "opponents": [
+    {
+      "id": 2,
+      "score": 100,
+      "worms": [
+        { "id": 1, "health": 100, "position": { "x": 1, "y": 1 }, "diggingRange": 1, "movementRange": 1 }
+      ]
+    }
+  ],
+  "map": [
+    [
+      { "x": 0, "y": 0, "type": "AIR" },
+      { "x": 1, "y": 0, "type": "AIR", "powerup": { "type": "HEALTH_PACK", "value": 5 } }
+    ],
+    [
+      { "x": 0, "y": 1, "type": "DIRT" },
+      { "x": 1, "y": 1, "type": "AIR", "occupier": { "id": 1, "playerId": 2, "health": 100, "position": { "x": 1, "y": 1 }, "diggingRange": 1, "movementRange": 1 } }
+    ]
+  ]
+}"#).unwrap();
+
+        let board = parsed.to_game_state();
+
+        assert_eq!(board.map, vec!{ CellType::Air, CellType::Air, CellType::Dirt, CellType::Air });
+        assert_eq!(board.powerups, vec!{ (Position { x: 1, y: 0 }, Powerup { powerup_type: PowerupType::HealthPack, value: 5 }) });
+        assert_eq!(board.players[0].worms[0].weapon, Some(Weapon { damage: 1, range: 3 }));
+        assert_eq!(board.players[1].worms[0].weapon, None);
+    }
+
+    #[test]
+    fn direction_to_finds_the_eight_aligned_directions() {
+        let origin = Position { x: 5, y: 5 };
+
+        assert_eq!(origin.direction_to(&Position { x: 5, y: 2 }), Some(Direction::N));
+        assert_eq!(origin.direction_to(&Position { x: 8, y: 2 }), Some(Direction::NE));
+        assert_eq!(origin.direction_to(&Position { x: 8, y: 5 }), Some(Direction::E));
+        assert_eq!(origin.direction_to(&Position { x: 8, y: 8 }), Some(Direction::SE));
+        assert_eq!(origin.direction_to(&Position { x: 5, y: 8 }), Some(Direction::S));
+        assert_eq!(origin.direction_to(&Position { x: 2, y: 8 }), Some(Direction::SW));
+        assert_eq!(origin.direction_to(&Position { x: 2, y: 5 }), Some(Direction::W));
+        assert_eq!(origin.direction_to(&Position { x: 2, y: 2 }), Some(Direction::NW));
+    }
+
+    #[test]
+    fn direction_to_is_none_when_not_aligned_or_identical() {
+        let origin = Position { x: 5, y: 5 };
+
+        assert_eq!(origin.direction_to(&Position { x: 7, y: 8 }), None);
+        assert_eq!(origin.direction_to(&Position { x: 5, y: 5 }), None);
+    }
 }
@@ -0,0 +1,451 @@
+use crate::board::{GameBoard, Worm};
+use crate::command::Command;
+use crate::json::{CellType, Position, PowerupType};
+
+/// Fixed health cost the engine charges both worms involved in a collision
+/// (a swap, a head-on move, or a move onto a worm that stayed put).
+const COLLISION_DAMAGE: u32 = 20;
+
+impl GameBoard {
+    /// Advances the board one round by applying both players' chosen
+    /// commands for their currently active worm: moves (including
+    /// swap/collision resolution), digs, shots, health-pack pickups, and
+    /// the `consecutive_do_nothing_count` streak.
+    ///
+    /// The turn order that picks which worm is "active" is synchronised
+    /// between both players, so the opponent's active worm for this round
+    /// shares `self.current_worm_id` with our own.
+    ///
+    /// Invalid commands (an out-of-range move, a dig out of range, ...)
+    /// are simply dropped, same as the engine does, rather than panicking.
+    pub fn apply(&self, my_command: Command, opponent_command: Command) -> GameBoard {
+        let worm_id = self.current_worm_id;
+        let my_command_is_effective = self.command_is_effective(0, worm_id, &my_command);
+
+        let mut board = self.clone();
+
+        board.resolve_moves(worm_id, &my_command, &opponent_command);
+        board.remove_dead_worms();
+
+        board.resolve_dig(0, worm_id, &my_command);
+        board.resolve_dig(1, worm_id, &opponent_command);
+
+        board.resolve_shot(0, worm_id, &my_command);
+        board.remove_dead_worms();
+        board.resolve_shot(1, worm_id, &opponent_command);
+        board.remove_dead_worms();
+
+        board.resolve_pickups();
+
+        board.consecutive_do_nothing_count = if my_command_is_effective {
+            0
+        } else {
+            board.consecutive_do_nothing_count + 1
+        };
+        board.current_round += 1;
+
+        board
+    }
+
+    /// Whether `command` is one the engine would actually act on for this
+    /// worm: a `Move`/`Dig` within range (and, for a move, onto `Air`), or
+    /// a `Shoot`. `DoNothing`, and anything rejected as out of range,
+    /// counts as taking no action for `consecutive_do_nothing_count`.
+    fn command_is_effective(&self, player_index: usize, worm_id: u32, command: &Command) -> bool {
+        let worm = match self.players[player_index].worms.iter().find(|w| w.id == worm_id) {
+            Some(worm) => worm,
+            None => return false
+        };
+
+        match command {
+            Command::DoNothing => false,
+            Command::Move(..) => self.valid_move_target(worm, command).is_some(),
+            Command::Dig(x, y) => {
+                let target = Position { x: *x as i32, y: *y as i32 };
+                worm.position.chebyshev_distance(&target) as u32 <= worm.digging_range
+            }
+            Command::Shoot(_) => true
+        }
+    }
+
+    /// Returns `command`'s `Move` destination if it's within the worm's
+    /// `movement_range`, lands on an on-map `Air` cell, and isn't already
+    /// occupied by a worm that isn't one of this round's two active worms
+    /// (those are resolved via swap/collision in `resolve_moves` instead).
+    /// `None` otherwise.
+    fn valid_move_target(&self, worm: &Worm, command: &Command) -> Option<Position> {
+        let (x, y) = match command {
+            Command::Move(x, y) => (*x, *y),
+            _ => return None
+        };
+        let target = Position { x: x as i32, y: y as i32 };
+
+        if worm.position.chebyshev_distance(&target) as u32 > worm.movement_range {
+            return None;
+        }
+
+        match self.cell_at(&target) {
+            Some(CellType::Air) => {}
+            _ => return None
+        }
+
+        let blocked_by_bystander = self.players.iter()
+            .flat_map(|p| p.worms.iter())
+            .any(|w| w.position == target && w.id != self.current_worm_id);
+
+        if blocked_by_bystander {
+            return None;
+        }
+
+        Some(target)
+    }
+
+    /// Moves both worms to their commanded destinations. If the worms would
+    /// swap places, move onto the same cell, or one moves onto the other
+    /// (which stayed put), that's a collision instead: neither worm moves,
+    /// and both take `COLLISION_DAMAGE`.
+    fn resolve_moves(&mut self, worm_id: u32, my_command: &Command, opponent_command: &Command) {
+        // A worm already removed this round (see `remove_dead_worms`) has
+        // no entry to look up here, and simply can't move or be collided
+        // with, so these are lookups rather than `self.worm`'s panicking
+        // ones.
+        let my_worm = self.players[0].worms.iter().find(|w| w.id == worm_id).cloned();
+        let opponent_worm = self.players[1].worms.iter().find(|w| w.id == worm_id).cloned();
+
+        let my_target = my_worm.as_ref().and_then(|w| self.valid_move_target(w, my_command));
+        let opponent_target = opponent_worm.as_ref().and_then(|w| self.valid_move_target(w, opponent_command));
+
+        let collided = match (&my_target, &opponent_target) {
+            (Some(mine), Some(theirs)) => {
+                let swapping = *mine == opponent_worm.as_ref().unwrap().position && *theirs == my_worm.as_ref().unwrap().position;
+                let head_on = mine == theirs;
+                swapping || head_on
+            }
+            (Some(mine), None) => opponent_worm.as_ref().is_some_and(|w| *mine == w.position),
+            (None, Some(theirs)) => my_worm.as_ref().is_some_and(|w| *theirs == w.position),
+            (None, None) => false
+        };
+
+        if collided {
+            self.collide(0, worm_id, 1, worm_id);
+            return;
+        }
+
+        if let Some(target) = my_target {
+            self.worm_mut(0, worm_id).position = target;
+        }
+        if let Some(target) = opponent_target {
+            self.worm_mut(1, worm_id).position = target;
+        }
+    }
+
+    /// Deals `COLLISION_DAMAGE` to both worms involved in a collision.
+    fn collide(&mut self, a_index: usize, a_id: u32, b_index: usize, b_id: u32) {
+        let a_health = self.worm(a_index, a_id).health.saturating_sub(COLLISION_DAMAGE);
+        let b_health = self.worm(b_index, b_id).health.saturating_sub(COLLISION_DAMAGE);
+        self.worm_mut(a_index, a_id).health = a_health;
+        self.worm_mut(b_index, b_id).health = b_health;
+    }
+
+    /// Turns a `Dirt` cell within the worm's `digging_range` into `Air`.
+    /// A no-op if the worm already died earlier this round.
+    fn resolve_dig(&mut self, player_index: usize, worm_id: u32, command: &Command) {
+        let (x, y) = match command {
+            Command::Dig(x, y) => (*x, *y),
+            _ => return
+        };
+
+        let worm = match self.players[player_index].worms.iter().find(|w| w.id == worm_id) {
+            Some(worm) => worm.clone(),
+            None => return
+        };
+
+        let target = Position { x: x as i32, y: y as i32 };
+        if worm.position.chebyshev_distance(&target) as u32 > worm.digging_range {
+            return;
+        }
+
+        if let Some(index) = self.cell_index(&target) {
+            if self.map[index] == CellType::Dirt {
+                self.map[index] = CellType::Air;
+            }
+        }
+    }
+
+    /// Traces the shooter's weapon along `direction` up to its range,
+    /// stopping at the map edge or the first non-`Air` cell, and damages
+    /// the first opposing worm hit. A no-op if the shooter already died
+    /// earlier this round.
+    fn resolve_shot(&mut self, shooter_index: usize, worm_id: u32, command: &Command) {
+        let direction = match command {
+            Command::Shoot(direction) => *direction,
+            _ => return
+        };
+
+        let worm = match self.players[shooter_index].worms.iter().find(|w| w.id == worm_id) {
+            Some(worm) => worm.clone(),
+            None => return
+        };
+        let target_index = 1 - shooter_index;
+        let weapon = worm.weapon.expect("A worm that can shoot must have a weapon");
+
+        for distance in 1..=weapon.range as i32 {
+            let position = worm.position.step(direction, distance);
+
+            let cell_type = match self.cell_at(&position) {
+                Some(cell_type) => cell_type.clone(),
+                None => break
+            };
+
+            let hit = self.players[target_index].worms.iter_mut()
+                .find(|w| w.position == position);
+
+            if let Some(target) = hit {
+                target.health = target.health.saturating_sub(weapon.damage);
+                break;
+            }
+
+            if cell_type != CellType::Air {
+                break;
+            }
+        }
+    }
+
+    /// Removes any worm reduced to 0 health, so it no longer blocks rays
+    /// or moves, and takes no further action this round.
+    fn remove_dead_worms(&mut self) {
+        for player in self.players.iter_mut() {
+            player.worms.retain(|w| w.health > 0);
+        }
+    }
+
+    /// Applies any health pack a worm is now standing on, and removes it
+    /// from the board.
+    fn resolve_pickups(&mut self) {
+        let powerups = std::mem::take(&mut self.powerups);
+
+        for (position, powerup) in powerups {
+            let picked_up_by = self.players.iter_mut()
+                .flat_map(|p| p.worms.iter_mut())
+                .find(|w| w.position == position);
+
+            match (picked_up_by, &powerup.powerup_type) {
+                (Some(worm), PowerupType::HealthPack) => {
+                    worm.health += powerup.value;
+                }
+                _ => self.powerups.push((position, powerup))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::Player;
+    use crate::command::Direction;
+    use crate::json::{JsonState, Weapon};
+
+    fn worm(id: u32, x: i32, y: i32) -> Worm {
+        Worm {
+            id,
+            health: 100,
+            position: Position { x, y },
+            digging_range: 1,
+            movement_range: 1,
+            weapon: Some(Weapon { damage: 20, range: 4 })
+        }
+    }
+
+    fn board(my_worm: Worm, opponent_worm: Worm, map: Vec<CellType>) -> GameBoard {
+        GameBoard {
+            current_round: 3,
+            max_rounds: 200,
+            map_size: 5,
+            current_worm_id: 1,
+            consecutive_do_nothing_count: 0,
+            players: [
+                Player { id: 1, score: 100, worms: vec!{ my_worm } },
+                Player { id: 2, score: 100, worms: vec!{ opponent_worm } }
+            ],
+            powerups: Vec::new(),
+            map
+        }
+    }
+
+    #[test]
+    fn move_relocates_each_worm_to_its_target() {
+        let board = board(worm(1, 0, 0), worm(1, 4, 4), vec![CellType::Air; 25]);
+
+        let next = board.apply(Command::Move(1, 0), Command::Move(4, 3));
+
+        assert_eq!(next.worm(0, 1).position, Position { x: 1, y: 0 });
+        assert_eq!(next.worm(1, 1).position, Position { x: 4, y: 3 });
+        assert_eq!(next.current_round, 4);
+    }
+
+    #[test]
+    fn move_beyond_movement_range_is_rejected() {
+        let board = board(worm(1, 0, 0), worm(1, 4, 4), vec![CellType::Air; 25]);
+
+        let next = board.apply(Command::Move(2, 2), Command::DoNothing);
+
+        assert_eq!(next.worm(0, 1).position, Position { x: 0, y: 0 });
+        assert_eq!(next.consecutive_do_nothing_count, 1);
+    }
+
+    #[test]
+    fn move_onto_non_air_terrain_is_rejected() {
+        let mut map = vec![CellType::Air; 25];
+        map[1] = CellType::Dirt;
+        let board = board(worm(1, 0, 0), worm(1, 4, 4), map);
+
+        let next = board.apply(Command::Move(1, 0), Command::DoNothing);
+
+        assert_eq!(next.worm(0, 1).position, Position { x: 0, y: 0 });
+    }
+
+    #[test]
+    fn swapping_worms_stay_put_and_take_collision_damage() {
+        let board = board(worm(1, 0, 0), worm(1, 1, 0), vec![CellType::Air; 25]);
+
+        let next = board.apply(Command::Move(1, 0), Command::Move(0, 0));
+
+        assert_eq!(next.worm(0, 1).position, Position { x: 0, y: 0 });
+        assert_eq!(next.worm(1, 1).position, Position { x: 1, y: 0 });
+        assert_eq!(next.worm(0, 1).health, 80);
+        assert_eq!(next.worm(1, 1).health, 80);
+    }
+
+    #[test]
+    fn colliding_worms_stay_put_and_take_collision_damage() {
+        let board = board(worm(1, 0, 0), worm(1, 2, 0), vec![CellType::Air; 25]);
+
+        let next = board.apply(Command::Move(1, 0), Command::Move(1, 0));
+
+        assert_eq!(next.worm(0, 1).position, Position { x: 0, y: 0 });
+        assert_eq!(next.worm(1, 1).position, Position { x: 2, y: 0 });
+        assert_eq!(next.worm(0, 1).health, 80);
+        assert_eq!(next.worm(1, 1).health, 80);
+    }
+
+    #[test]
+    fn moving_onto_a_stationary_worm_is_a_collision_too() {
+        let board = board(worm(1, 0, 0), worm(1, 1, 0), vec![CellType::Air; 25]);
+
+        let next = board.apply(Command::Move(1, 0), Command::DoNothing);
+
+        assert_eq!(next.worm(0, 1).position, Position { x: 0, y: 0 });
+        assert_eq!(next.worm(1, 1).position, Position { x: 1, y: 0 });
+        assert_eq!(next.worm(0, 1).health, 80);
+        assert_eq!(next.worm(1, 1).health, 80);
+    }
+
+    #[test]
+    fn dig_clears_dirt_within_range() {
+        let mut map = vec![CellType::Air; 25];
+        map[1] = CellType::Dirt;
+        let board = board(worm(1, 0, 0), worm(1, 4, 4), map);
+
+        let next = board.apply(Command::Dig(1, 0), Command::DoNothing);
+
+        assert_eq!(next.cell_at(&Position { x: 1, y: 0 }), Some(&CellType::Air));
+    }
+
+    #[test]
+    fn dig_beyond_digging_range_counts_as_no_action() {
+        let mut map = vec![CellType::Air; 25];
+        map[2] = CellType::Dirt;
+        let board = board(worm(1, 0, 0), worm(1, 4, 4), map);
+
+        let next = board.apply(Command::Dig(2, 0), Command::DoNothing);
+
+        assert_eq!(next.cell_at(&Position { x: 2, y: 0 }), Some(&CellType::Dirt));
+        assert_eq!(next.consecutive_do_nothing_count, 1);
+    }
+
+    #[test]
+    fn shoot_damages_the_first_worm_on_the_ray() {
+        let board = board(worm(1, 0, 0), worm(1, 3, 0), vec![CellType::Air; 25]);
+
+        let next = board.apply(Command::Shoot(Direction::E), Command::DoNothing);
+
+        assert_eq!(next.worm(1, 1).health, 80);
+    }
+
+    #[test]
+    fn shoot_is_blocked_by_dirt() {
+        let mut map = vec![CellType::Air; 25];
+        map[2] = CellType::Dirt;
+        let board = board(worm(1, 0, 0), worm(1, 3, 0), map);
+
+        let next = board.apply(Command::Shoot(Direction::E), Command::DoNothing);
+
+        assert_eq!(next.worm(1, 1).health, 100);
+    }
+
+    #[test]
+    fn a_worm_killed_this_round_is_removed_and_does_not_retaliate() {
+        let mut opponent = worm(1, 3, 0);
+        opponent.health = 15;
+        let board = board(worm(1, 0, 0), opponent, vec![CellType::Air; 25]);
+
+        let next = board.apply(Command::Shoot(Direction::E), Command::Shoot(Direction::W));
+
+        assert!(next.players[1].worms.is_empty());
+        assert_eq!(next.worm(0, 1).health, 100);
+    }
+
+    #[test]
+    fn walking_onto_a_health_pack_heals_and_consumes_it() {
+        let mut board = board(worm(1, 0, 0), worm(1, 4, 4), vec![CellType::Air; 25]);
+        board.players[0].worms[0].health = 50;
+        board.powerups.push((Position { x: 1, y: 0 }, crate::json::Powerup {
+            powerup_type: PowerupType::HealthPack,
+            value: 10
+        }));
+
+        let next = board.apply(Command::Move(1, 0), Command::DoNothing);
+
+        assert_eq!(next.worm(0, 1).health, 60);
+        assert_eq!(next.powerups.len(), 0);
+    }
+
+    #[test]
+    fn consecutive_do_nothing_count_tracks_our_own_streak() {
+        let board = board(worm(1, 0, 0), worm(1, 4, 4), vec![CellType::Air; 25]);
+
+        let after_one = board.apply(Command::DoNothing, Command::DoNothing);
+        assert_eq!(after_one.consecutive_do_nothing_count, 1);
+
+        let after_two = after_one.apply(Command::DoNothing, Command::DoNothing);
+        assert_eq!(after_two.consecutive_do_nothing_count, 2);
+
+        let reset = after_two.apply(Command::Move(1, 0), Command::DoNothing);
+        assert_eq!(reset.consecutive_do_nothing_count, 0);
+    }
+
+    // No competition match log ships with this tree (and none is reachable
+    // from here), so these three rounds are a hand-built trace rather than
+    // a captured match replay. To still catch the kind of divergence a real
+    // replay would, each transition below chains several rules at once the
+    // way a real round does: round 0 -> 1 applies a kill (dead-worm removal)
+    // alongside a dig rejected for being out of range, and round 1 -> 2
+    // applies a move that lands on and consumes a health pack. Swap in a
+    // real recorded-match trace under `fixtures/` if one ever becomes
+    // available.
+    #[test]
+    fn apply_reproduces_the_next_two_fixture_rounds() {
+        let round_0: JsonState = serde_json::from_str(include_str!("fixtures/round_0.json")).unwrap();
+        let round_1: JsonState = serde_json::from_str(include_str!("fixtures/round_1.json")).unwrap();
+        let round_2: JsonState = serde_json::from_str(include_str!("fixtures/round_2.json")).unwrap();
+
+        let board = round_0.to_game_state();
+
+        let after_round_0 = board.apply(Command::Shoot(Direction::E), Command::Dig(2, 2));
+        assert_eq!(after_round_0, round_1.to_game_state());
+
+        let after_round_1 = after_round_0.apply(Command::Move(1, 0), Command::DoNothing);
+        assert_eq!(after_round_1, round_2.to_game_state());
+    }
+}